@@ -0,0 +1,13 @@
+//! Captures the compiler version at build time so `X-Stripe-Client-User-Agent` can report
+//! `lang_version` without a runtime dependency.
+
+fn main() {
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let version = std::process::Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .unwrap_or_else(|| "unknown".into());
+    println!("cargo:rustc-env=RUSTC_VERSION={}", version.trim());
+}
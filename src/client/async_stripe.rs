@@ -0,0 +1,376 @@
+use super::base::{
+    advance_cursor, apply_default_limit, client_user_agent, is_retryable_status, retry_delay, serialize_form,
+    url, url_with_params, user_agent, AppInfo, Identifiable, Paginable, RetryConfig,
+};
+use crate::config::Response;
+use crate::error::{Error, ErrorResponse, RequestError};
+use crate::params::{Headers, List};
+use async_stream::try_stream;
+use futures_util::stream::Stream;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use reqwest::RequestBuilder;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct Client {
+    client: reqwest::Client,
+    secret_key: String,
+    headers: Headers,
+    host: String,
+    retry_config: RetryConfig,
+    timeout: Option<Duration>,
+}
+
+impl Client {
+    /// Creates a new client pointed to `https://api.stripe.com/`
+    pub fn new(secret_key: impl Into<String>) -> Client {
+        Client::from_url("https://api.stripe.com/", secret_key)
+    }
+
+    /// Creates a new client posted to a custom `scheme://host/`
+    pub fn from_url(scheme_host: impl Into<String>, secret_key: impl Into<String>) -> Client {
+        Client::from_builder_and_url(default_http_client(), scheme_host, secret_key)
+    }
+
+    /// Creates a client using a caller-supplied `reqwest::Client`, pointed to
+    /// `https://api.stripe.com/`.
+    ///
+    /// Use this to inject a fully custom HTTP client (proxy settings, connection pool sizing,
+    /// custom root certificates) instead of the one built from the `with-rustls`/`with-native-tls`
+    /// features.
+    pub fn from_builder(client: reqwest::Client, secret_key: impl Into<String>) -> Client {
+        Client::from_builder_and_url(client, "https://api.stripe.com/", secret_key)
+    }
+
+    fn from_builder_and_url(
+        client: reqwest::Client,
+        scheme_host: impl Into<String>,
+        secret_key: impl Into<String>,
+    ) -> Client {
+        let url = scheme_host.into();
+        let host = if url.ends_with('/') { format!("{}v1", url) } else { format!("{}/v1", url) };
+        Client {
+            client,
+            secret_key: secret_key.into(),
+            headers: Headers::default(),
+            host,
+            retry_config: RetryConfig::default(),
+            timeout: None,
+        }
+    }
+
+    /// Clones a new client with different headers.
+    ///
+    /// This is the recommended way to send requests for many different Stripe accounts
+    /// or with different Meta, Extra, and Expand headers while using the same secret key.
+    pub fn with_headers(&self, headers: Headers) -> Client {
+        let mut client = self.clone();
+        client.headers = headers;
+        client
+    }
+
+    /// Clones a new client that applies `timeout` to every request it sends, overriding the
+    /// default set with `set_default_timeout`. Useful for giving a single slow-but-expected
+    /// call (e.g. a large file upload) more headroom than the rest of the client's calls.
+    pub fn with_timeout(&self, timeout: Duration) -> Client {
+        let mut client = self.clone();
+        client.timeout = Some(timeout);
+        client
+    }
+
+    /// Sets a value for the Stripe-Account header
+    ///
+    /// This is recommended if you are acting as only one Account for the lifetime of the client.
+    /// Otherwise, prefer `client.with(Headers{stripe_account: "acct_ABC", ..})`.
+    pub fn set_stripe_account<S: Into<String>>(&mut self, account_id: S) {
+        self.headers.stripe_account = Some(account_id.into());
+    }
+
+    /// Identifies a plugin or integration built on this crate in the `User-Agent` and
+    /// `X-Stripe-Client-User-Agent` headers, as required for Stripe's Verified Partner program.
+    pub fn set_app_info(&mut self, app_info: AppInfo) {
+        self.headers.app_info = Some(app_info);
+    }
+
+    /// Overrides the retry behavior used for every request sent by this client (default:
+    /// up to 4 attempts, 500ms base backoff, 8s max backoff).
+    pub fn set_retry_config(&mut self, retry_config: RetryConfig) {
+        self.retry_config = retry_config;
+    }
+
+    /// Sets the timeout applied to every request sent by this client (default: none, i.e.
+    /// `reqwest`'s own default of no timeout). Use `with_timeout` to override it for a single
+    /// call instead.
+    pub fn set_default_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Make a `GET` http request with just a path
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Response<T> {
+        let url = self.url(path);
+        let headers = self.headers(None);
+        let timeout = self.timeout;
+        send(|| with_timeout(self.client.get(&url).headers(headers.clone()), timeout), &self.retry_config).await
+    }
+
+    /// Make a `GET` http request with url query parameters
+    pub async fn get_query<T: DeserializeOwned, P: serde::Serialize>(
+        &self,
+        path: &str,
+        params: P,
+    ) -> Response<T> {
+        let url = self.url_with_params(path, params)?;
+        let headers = self.headers(None);
+        let timeout = self.timeout;
+        send(|| with_timeout(self.client.get(&url).headers(headers.clone()), timeout), &self.retry_config).await
+    }
+
+    /// Make a `DELETE` http request with just a path
+    pub async fn delete<T: DeserializeOwned>(&self, path: &str) -> Response<T> {
+        let url = self.url(path);
+        let headers = self.headers(None);
+        let timeout = self.timeout;
+        send(|| with_timeout(self.client.delete(&url).headers(headers.clone()), timeout), &self.retry_config).await
+    }
+
+    /// Make a `DELETE` http request with url query parameters
+    pub async fn delete_query<T: DeserializeOwned, P: serde::Serialize>(
+        &self,
+        path: &str,
+        params: P,
+    ) -> Response<T> {
+        let url = self.url_with_params(path, params)?;
+        let headers = self.headers(None);
+        let timeout = self.timeout;
+        send(|| with_timeout(self.client.delete(&url).headers(headers.clone()), timeout), &self.retry_config).await
+    }
+
+    /// Make a `POST` http request with just a path, retried safely under an auto-generated
+    /// idempotency key.
+    pub async fn post<T: DeserializeOwned>(&self, path: &str) -> Response<T> {
+        self.post_with_idempotency_key(path, Uuid::new_v4().to_string()).await
+    }
+
+    /// Make a `POST` http request with just a path under the given idempotency key, so a
+    /// retry (yours or ours) can't be applied twice.
+    pub async fn post_with_idempotency_key<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        idempotency_key: impl Into<String>,
+    ) -> Response<T> {
+        let url = self.url(path);
+        let headers = self.headers(Some(&idempotency_key.into()));
+        let timeout = self.timeout;
+        send(|| with_timeout(self.client.post(&url).headers(headers.clone()), timeout), &self.retry_config).await
+    }
+
+    /// Make a `POST` http request with urlencoded body, retried safely under an auto-generated
+    /// idempotency key.
+    pub async fn post_form<T: DeserializeOwned, F: serde::Serialize>(
+        &self,
+        path: &str,
+        form: F,
+    ) -> Response<T> {
+        self.post_form_with_idempotency_key(path, form, Uuid::new_v4().to_string()).await
+    }
+
+    /// Make a `POST` http request with urlencoded body under the given idempotency key, so a
+    /// retry (yours or ours) can't double-apply the request.
+    pub async fn post_form_with_idempotency_key<T: DeserializeOwned, F: serde::Serialize>(
+        &self,
+        path: &str,
+        form: F,
+        idempotency_key: impl Into<String>,
+    ) -> Response<T> {
+        let url = self.url(path);
+        let headers = self.headers(Some(&idempotency_key.into()));
+        let body = serialize_form(&form)?;
+        let timeout = self.timeout;
+        send(
+            || {
+                with_timeout(
+                    self.client
+                        .post(&url)
+                        .headers(headers.clone())
+                        .header(reqwest::header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+                        .body(body.clone()),
+                    timeout,
+                )
+            },
+            &self.retry_config,
+        )
+        .await
+    }
+
+    /// Streams every item across all pages of a Stripe list endpoint, threading the
+    /// `starting_after` cursor automatically as each page runs out.
+    pub fn paginate<T, P>(&self, path: &str, mut params: P) -> impl Stream<Item = Result<T, Error>>
+    where
+        T: DeserializeOwned + Identifiable,
+        P: Paginable + serde::Serialize + Clone,
+    {
+        apply_default_limit(&mut params);
+        let client = self.clone();
+        let path = path.to_string();
+        try_stream! {
+            let mut params = params;
+            let mut has_more = true;
+            while has_more {
+                let page: List<T> = client.get_query(&path, params.clone()).await?;
+                has_more = advance_cursor(&page.data, page.has_more, &mut params);
+                for item in page.data {
+                    yield item;
+                }
+            }
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        url(&self.host, path)
+    }
+
+    fn url_with_params<P: serde::Serialize>(&self, path: &str, params: P) -> Result<String, Error> {
+        url_with_params(&self.host, path, params)
+    }
+
+    fn headers(&self, idempotency_key: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            reqwest::header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", self.secret_key)).unwrap(),
+        );
+        if let Some(account) = &self.headers.stripe_account {
+            headers.insert(
+                HeaderName::from_static("stripe-account"),
+                HeaderValue::from_str(account).unwrap(),
+            );
+        }
+        if let Some(client_id) = &self.headers.client_id {
+            headers.insert(
+                HeaderName::from_static("client-id"),
+                HeaderValue::from_str(client_id).unwrap(),
+            );
+        }
+        if let Some(stripe_version) = &self.headers.stripe_version {
+            headers.insert(
+                HeaderName::from_static("stripe-version"),
+                HeaderValue::from_str(stripe_version.as_str()).unwrap(),
+            );
+        }
+        if let Some(idempotency_key) = idempotency_key {
+            headers.insert(
+                HeaderName::from_static("idempotency-key"),
+                HeaderValue::from_str(idempotency_key).unwrap(),
+            );
+        }
+        headers.insert(
+            reqwest::header::USER_AGENT,
+            HeaderValue::from_str(&user_agent(&self.headers.app_info)).unwrap(),
+        );
+        if let Ok(client_user_agent) = client_user_agent(&self.headers.app_info) {
+            headers.insert(
+                HeaderName::from_static("x-stripe-client-user-agent"),
+                HeaderValue::from_str(&client_user_agent).unwrap(),
+            );
+        }
+        headers
+    }
+}
+
+/// Builds the default `reqwest::Client`, selecting a TLS backend based on the
+/// `with-rustls`/`with-native-tls` cargo features.
+fn default_http_client() -> reqwest::Client {
+    let builder = reqwest::ClientBuilder::new();
+    #[cfg(feature = "with-rustls")]
+    let builder = builder.use_rustls_tls();
+    #[cfg(feature = "with-native-tls")]
+    let builder = builder.use_native_tls();
+    builder.build().expect("failed to initialize the default reqwest client")
+}
+
+/// Applies a per-request timeout override, if any, to `request`.
+fn with_timeout(request: RequestBuilder, timeout: Option<Duration>) -> RequestBuilder {
+    match timeout {
+        Some(timeout) => request.timeout(timeout),
+        None => request,
+    }
+}
+
+/// Sends the request built by `build`, retrying connection errors, failed body reads, 409s,
+/// 429s, and 5xxs with capped exponential backoff, for at most `retry_config.max_attempts`
+/// attempts in total. A `Retry-After` response header sets the delay; `Stripe-Should-Retry:
+/// false` suppresses a retry and `Stripe-Should-Retry: true` forces one for any failed status.
+///
+/// A timed-out request is surfaced immediately as `Error::Timeout` rather than retried, since
+/// the caller is in the best position to decide whether to try again.
+async fn send<T: DeserializeOwned>(
+    build: impl Fn() -> RequestBuilder,
+    retry_config: &RetryConfig,
+) -> Response<T> {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let response = match build().send().await {
+            Ok(response) => response,
+            Err(err) => {
+                if err.is_timeout() {
+                    return Err(Error::timeout(err));
+                }
+                if attempt < retry_config.max_attempts {
+                    tokio::time::sleep(retry_delay(attempt, retry_config, None)).await;
+                    continue;
+                }
+                return Err(Error::from(err));
+            }
+        };
+
+        let status = response.status();
+        let retry_after = parse_retry_after(response.headers());
+        let stripe_should_retry =
+            response.headers().get("stripe-should-retry").and_then(|v| v.to_str().ok());
+        let should_retry = stripe_should_retry == Some("true")
+            || (is_retryable_status(status.as_u16()) && stripe_should_retry != Some("false"));
+
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(err) => {
+                if err.is_timeout() {
+                    return Err(Error::timeout(err));
+                }
+                if attempt < retry_config.max_attempts {
+                    tokio::time::sleep(retry_delay(attempt, retry_config, retry_after)).await;
+                    continue;
+                }
+                return Err(Error::from(err));
+            }
+        };
+
+        // N.B. For debugging
+        // eprintln!("request was: {}", body);
+        if !status.is_success() {
+            if should_retry && attempt < retry_config.max_attempts {
+                tokio::time::sleep(retry_delay(attempt, retry_config, retry_after)).await;
+                continue;
+            }
+            let mut err = serde_json::from_str(&body).unwrap_or_else(|err| {
+                let mut req = ErrorResponse { error: RequestError::default() };
+                req.error.message = Some(format!("failed to deserialize error: {}", err));
+                req
+            });
+            err.error.http_status = status.as_u16();
+            return Err(Error::from(err.error));
+        }
+
+        return serde_json::from_str(&body).map_err(Error::deserialize);
+    }
+}
+
+fn parse_retry_after(headers: &HeaderMap) -> Option<std::time::Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(std::time::Duration::from_secs)
+}
@@ -0,0 +1,270 @@
+use crate::error::Error;
+use std::time::Duration;
+
+/// Tunable retry behavior for transient failures (connection errors, 409 lock conflicts,
+/// 429s, and 5xxs).
+///
+/// `GET`/`DELETE` requests are always safe to retry; `POST` requests are only retried when
+/// sent with an idempotency key, so a retried charge can't be double-applied.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryConfig {
+    /// Total number of attempts, including the first. A request that keeps failing stops
+    /// retrying once this is reached.
+    pub max_attempts: u32,
+    /// Base delay used for the exponential backoff before jitter is applied.
+    pub base_delay: Duration,
+    /// Upper bound on the computed backoff delay (a `Retry-After` from Stripe can still
+    /// exceed this; Stripe's own guidance is honored when present).
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+/// Whether an HTTP status returned by Stripe is worth retrying.
+pub(crate) fn is_retryable_status(status: u16) -> bool {
+    status == 409 || status == 429 || (500..600).contains(&status)
+}
+
+/// Computes the delay before the `attempt`th retry (1-indexed: the delay before the second
+/// overall attempt is `retry_delay(1, ..)`).
+///
+/// Honors a server-provided `Retry-After` hint when present; otherwise applies capped
+/// exponential backoff with jitter so concurrent retries don't land in lockstep.
+pub(crate) fn retry_delay(attempt: u32, config: &RetryConfig, retry_after: Option<Duration>) -> Duration {
+    if let Some(retry_after) = retry_after {
+        return retry_after;
+    }
+    let exp_ms = config.base_delay.as_millis().saturating_mul(1u128 << attempt.min(16));
+    let capped_ms = exp_ms.min(config.max_delay.as_millis()) as u64;
+    let jitter_ms = rand::Rng::gen_range(&mut rand::thread_rng(), 0..=capped_ms / 2);
+    Duration::from_millis((capped_ms / 2 + jitter_ms).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_honors_retry_after_verbatim_even_past_max_delay() {
+        let config = RetryConfig::default();
+        let retry_after = config.max_delay + Duration::from_secs(30);
+        assert_eq!(retry_delay(1, &config, Some(retry_after)), retry_after);
+    }
+
+    #[test]
+    fn retry_delay_backs_off_without_exceeding_max_delay() {
+        let config = RetryConfig::default();
+        for attempt in 1..10 {
+            let delay = retry_delay(attempt, &config, None);
+            assert!(delay <= config.max_delay);
+            assert!(delay >= Duration::from_millis(1));
+        }
+    }
+}
+
+/// Build the full request URL for `path` against `host`.
+pub(crate) fn url(host: &str, path: &str) -> String {
+    format!("{}/{}", host, &path[1..])
+}
+
+/// Build the full request URL for `path` against `host`, with `params` serialized as a query string.
+pub(crate) fn url_with_params<P: serde::Serialize>(
+    host: &str,
+    path: &str,
+    params: P,
+) -> Result<String, Error> {
+    let params = serde_qs::to_string(&params).map_err(Error::serialize)?;
+    Ok(format!("{}/{}?{}", host, &path[1..], params))
+}
+
+/// Serialize the form content using `serde_qs` instead of `serde_urlencoded`
+///
+/// See https://github.com/seanmonstar/reqwest/issues/274
+pub(crate) fn serialize_form<T: serde::Serialize>(form: &T) -> Result<String, Error> {
+    serde_qs::to_string(form).map_err(Error::serialize)
+}
+
+/// Identifies a plugin or integration built on top of this crate, so Stripe can attribute API
+/// traffic to it (required for Stripe's Verified Partner program).
+///
+/// Set via `Client::set_app_info` (or `Headers { app_info: Some(..), .. }`); rendered into the
+/// `User-Agent` and `X-Stripe-Client-User-Agent` headers on every request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct AppInfo {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl AppInfo {
+    /// Renders as `name`, `name/version`, `name (url)`, or `name/version (url)`, matching
+    /// Stripe's other bindings (e.g. `formatAppInfo` in stripe-php).
+    fn formatted(&self) -> String {
+        match (&self.version, &self.url) {
+            (Some(version), Some(url)) => format!("{}/{} ({})", self.name, version, url),
+            (Some(version), None) => format!("{}/{}", self.name, version),
+            (None, Some(url)) => format!("{} ({})", self.name, url),
+            (None, None) => self.name.clone(),
+        }
+    }
+}
+
+/// This crate's own version, reported to Stripe as `bindings_version`.
+pub(crate) const CLIENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Builds the `User-Agent` header value: `Stripe/v1 AsyncStripeBindings/<version>`, with the
+/// formatted `app_info` appended when set.
+pub(crate) fn user_agent(app_info: &Option<AppInfo>) -> String {
+    let mut ua = format!("Stripe/v1 AsyncStripeBindings/{}", CLIENT_VERSION);
+    if let Some(app_info) = app_info {
+        ua.push(' ');
+        ua.push_str(&app_info.formatted());
+    }
+    ua
+}
+
+#[derive(serde::Serialize)]
+struct ClientUserAgent<'a> {
+    bindings_version: &'static str,
+    lang: &'static str,
+    lang_version: &'static str,
+    publisher: &'static str,
+    uname: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    application: Option<&'a AppInfo>,
+}
+
+/// Builds the `X-Stripe-Client-User-Agent` header value: a JSON object carrying
+/// `bindings_version`, `lang`, `lang_version`, `publisher`, `uname`, and (when set) `application`.
+pub(crate) fn client_user_agent(app_info: &Option<AppInfo>) -> Result<String, Error> {
+    let ua = ClientUserAgent {
+        bindings_version: CLIENT_VERSION,
+        lang: "rust",
+        lang_version: env!("RUSTC_VERSION"),
+        publisher: "stripe",
+        uname: format!("{}/{}", std::env::consts::OS, std::env::consts::ARCH),
+        application: app_info.as_ref(),
+    };
+    serde_json::to_string(&ua).map_err(Error::serialize)
+}
+
+/// Implemented by Stripe object types (`Customer`, `Charge`, ...) so the pagination helper
+/// can read the cursor id off the last item of a page.
+pub trait Identifiable {
+    fn id(&self) -> String;
+}
+
+/// Implemented by list parameter types (`ListCustomers`, `ListCharges`, ...) so the
+/// pagination helper can advance the `starting_after` cursor between pages and apply a
+/// default page size when the caller didn't request one.
+pub trait Paginable {
+    fn set_starting_after(&mut self, id: String);
+
+    /// The `limit` the caller already set, if any.
+    fn limit(&self) -> Option<u64>;
+
+    fn set_limit(&mut self, limit: u64);
+}
+
+/// Page size `Client::paginate` requests when the caller's params don't already set a
+/// `limit`, to avoid falling back to Stripe's own default of 10 and multiplying round-trips.
+pub(crate) const DEFAULT_PAGE_LIMIT: u64 = 100;
+
+/// Applies `DEFAULT_PAGE_LIMIT` to `params` unless the caller already set a `limit`.
+pub(crate) fn apply_default_limit<P: Paginable>(params: &mut P) {
+    if params.limit().is_none() {
+        params.set_limit(DEFAULT_PAGE_LIMIT);
+    }
+}
+
+/// Advances `params`' `starting_after` cursor to the id of the last item in `data`, and
+/// returns whether another page should be fetched. Stops pagination if `data` is empty even
+/// when the server's `has_more` claims otherwise, so an inconsistent response can't cause an
+/// infinite loop of empty pages.
+pub(crate) fn advance_cursor<T: Identifiable, P: Paginable>(
+    data: &[T],
+    has_more: bool,
+    params: &mut P,
+) -> bool {
+    match data.last() {
+        Some(last) => {
+            params.set_starting_after(last.id());
+            has_more
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod pagination_tests {
+    use super::*;
+
+    #[derive(Clone, serde::Serialize)]
+    struct TestParams {
+        starting_after: Option<String>,
+        limit: Option<u64>,
+    }
+
+    impl Paginable for TestParams {
+        fn set_starting_after(&mut self, id: String) {
+            self.starting_after = Some(id);
+        }
+
+        fn limit(&self) -> Option<u64> {
+            self.limit
+        }
+
+        fn set_limit(&mut self, limit: u64) {
+            self.limit = Some(limit);
+        }
+    }
+
+    struct TestItem(&'static str);
+
+    impl Identifiable for TestItem {
+        fn id(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    #[test]
+    fn apply_default_limit_only_fills_in_when_unset() {
+        let mut params = TestParams { starting_after: None, limit: None };
+        apply_default_limit(&mut params);
+        assert_eq!(params.limit, Some(DEFAULT_PAGE_LIMIT));
+
+        let mut params = TestParams { starting_after: None, limit: Some(5) };
+        apply_default_limit(&mut params);
+        assert_eq!(params.limit, Some(5));
+    }
+
+    #[test]
+    fn advance_cursor_sets_starting_after_and_passes_through_has_more() {
+        let mut params = TestParams { starting_after: None, limit: None };
+        let data = [TestItem("a"), TestItem("b")];
+
+        assert!(advance_cursor(&data, true, &mut params));
+        assert_eq!(params.starting_after, Some("b".to_string()));
+
+        assert!(!advance_cursor(&data, false, &mut params));
+    }
+
+    #[test]
+    fn advance_cursor_stops_on_empty_data_even_if_has_more_is_true() {
+        let mut params = TestParams { starting_after: None, limit: None };
+        let data: [TestItem; 0] = [];
+
+        assert!(!advance_cursor(&data, true, &mut params));
+        assert_eq!(params.starting_after, None);
+    }
+}
@@ -0,0 +1,26 @@
+//! HTTP clients for talking to the Stripe API.
+//!
+//! Two implementations are provided behind mutually exclusive cargo features: a
+//! `blocking` client built on `reqwest::blocking`, and an `async` client built on
+//! reqwest's non-blocking API. Both expose the same `Client` surface (`get`,
+//! `get_query`, `post`, `post_form`, `delete`, `delete_query`) and share the
+//! request-building helpers in `base`; the async variant differs in awaiting the
+//! send, the body read, and the retry backoff.
+
+mod base;
+pub use self::base::{AppInfo, Identifiable, Paginable, RetryConfig};
+
+#[cfg(all(feature = "async", feature = "blocking"))]
+compile_error!("only one of the `async` or `blocking` client features may be enabled at a time");
+#[cfg(not(any(feature = "async", feature = "blocking")))]
+compile_error!("one of the `async` or `blocking` client features must be enabled");
+
+#[cfg(feature = "async")]
+mod async_stripe;
+#[cfg(feature = "async")]
+pub use self::async_stripe::Client;
+
+#[cfg(feature = "blocking")]
+mod blocking;
+#[cfg(feature = "blocking")]
+pub use self::blocking::{Client, Paginator};